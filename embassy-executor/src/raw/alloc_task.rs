@@ -1,9 +1,11 @@
 use alloc::sync::Arc;
+use core::cell::RefCell;
 use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
 use atomic_polyfill::{AtomicU32, Ordering};
+use critical_section::Mutex as CsMutex;
 use embassy_time::Instant;
 use futures_util::Future;
 
@@ -12,10 +14,60 @@ use super::util::{SyncUnsafeCell, UninitCell};
 use super::{timer_queue, wake_task, waker, TaskHeader, TaskRef, STATE_RUN_QUEUED, STATE_SPAWNED};
 use crate::SpawnToken;
 
+/// Set on `AllocTaskStorage::join_state` by [`JoinHandle::abort`]; the task's
+/// `poll` then drops the future instead of polling it. Kept separate from `TaskHeader::state`
+/// so it doesn't need to share bit allocation with the generic executor.
+const JOIN_ABORTED: u32 = 1;
+
+/// Error returned by a [`JoinHandle`] when the task was aborted before it
+/// completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinError {
+    _private: (),
+}
+
+/// Object-safe handle onto a spawned task's completion, type-erased over
+/// everything but its `Output`. Implemented by `AllocTaskStorage<F>` so a
+/// [`JoinHandle<F::Output>`] doesn't need to name `F`.
+trait Joinable<T> {
+    fn poll_join(&self, waker: &Waker) -> Poll<Result<T, JoinError>>;
+    fn abort(&self);
+}
+
+/// A handle to a task spawned with [`AllocTaskStorage::spawn`].
+///
+/// Awaiting it resolves to the task's output once it completes, or to
+/// [`JoinError`] if it was [aborted](JoinHandle::abort) first. Dropping the
+/// handle does not cancel the task; call `abort()` explicitly for that.
+pub struct JoinHandle<T: 'static> {
+    storage: Arc<dyn Joinable<T>>,
+}
+
+impl<T: 'static> JoinHandle<T> {
+    /// Cancel the task: its future is dropped in place (without being
+    /// polled to completion) the next time it would otherwise run, and this
+    /// handle resolves to [`JoinError`]. Has no effect on a task that has
+    /// already completed; the handle then still resolves to its output.
+    pub fn abort(&self) {
+        self.storage.abort()
+    }
+}
+
+impl<T: 'static> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.storage.poll_join(cx.waker())
+    }
+}
+
 #[repr(C)]
 pub struct AllocTaskStorage<F: Future + 'static> {
     raw: TaskHeader,
     future: UninitCell<F>,
+    output: SyncUnsafeCell<Option<F::Output>>,
+    join_state: AtomicU32,
+    joiner: CsMutex<RefCell<Option<Waker>>>,
 }
 
 impl<F: Future + 'static> AllocTaskStorage<F> {
@@ -39,7 +91,10 @@ impl<F: Future + 'static> AllocTaskStorage<F> {
     ///
     /// Once the task has finished running, you may spawn it again. It is allowed to spawn it
     /// on a different executor.
-    pub fn spawn(future: impl FnOnce() -> F) -> SpawnToken<impl Sized> {
+    ///
+    /// Also returns a [`JoinHandle`] for the spawned task, alongside the `SpawnToken`; the
+    /// handle is independent of the token and stays with whoever called `spawn`.
+    pub fn spawn(future: impl FnOnce() -> F) -> (SpawnToken<impl Sized>, JoinHandle<F::Output>) {
         let header = TaskHeader {
             state: AtomicU32::new(STATE_SPAWNED | STATE_RUN_QUEUED),
             run_queue_item: RunQueueItem::new(),
@@ -55,12 +110,17 @@ impl<F: Future + 'static> AllocTaskStorage<F> {
         let storage = Arc::new(AllocTaskStorage {
             raw: header,
             future: UninitCell::uninit(),
+            output: SyncUnsafeCell::new(None),
+            join_state: AtomicU32::new(0),
+            joiner: CsMutex::new(RefCell::new(None)),
         });
 
         unsafe {
             storage.future.write_in_place(future);
         }
 
+        let handle = JoinHandle { storage: storage.clone() };
+
         let storage_ptr = Arc::into_raw(storage);
 
         debug!("Arc: {}", storage_ptr);
@@ -68,7 +128,14 @@ impl<F: Future + 'static> AllocTaskStorage<F> {
         // NOTE(unsafe): #[repr(C)] allows us to cast between AllocTaskStorage and TaskHeader
         let task_ref = unsafe { TaskRef::from_ptr(storage_ptr as _) };
 
-        return unsafe { SpawnToken::<F>::new(task_ref) };
+        return (unsafe { SpawnToken::<F>::new(task_ref) }, handle);
+    }
+
+    fn wake_joiner(&self) {
+        let waker = critical_section::with(|cs| self.joiner.borrow(cs).borrow_mut().take());
+        if let Some(waker) = waker {
+            waker.wake();
+        }
     }
 
     unsafe fn waker(self: &Arc<Self>) -> Waker {
@@ -81,16 +148,31 @@ impl<F: Future + 'static> AllocTaskStorage<F> {
         // let this = &*(p.as_ptr() as *const AllocTaskStorage<F>);
         let this = Arc::from_raw(p.as_ptr() as *const AllocTaskStorage<F>);
 
+        if this.join_state.load(Ordering::Acquire) & JOIN_ABORTED != 0 {
+            this.future.drop_in_place();
+            this.raw.state.fetch_and(!STATE_SPAWNED, Ordering::AcqRel);
+
+            #[cfg(feature = "integrated-timers")]
+            this.raw.expires_at.set(Instant::MAX);
+
+            this.wake_joiner();
+            debug!("Ref count: {} {}", p.as_ptr(), Arc::strong_count(&this));
+            return;
+        }
+
         let future = Pin::new_unchecked(this.future.as_mut());
         let waker = this.waker();
         let mut cx = Context::from_waker(&waker);
         match future.poll(&mut cx) {
-            Poll::Ready(_) => {
+            Poll::Ready(output) => {
                 this.future.drop_in_place();
+                this.output.set(Some(output));
                 this.raw.state.fetch_and(!STATE_SPAWNED, Ordering::AcqRel);
 
                 #[cfg(feature = "integrated-timers")]
                 this.raw.expires_at.set(Instant::MAX);
+
+                this.wake_joiner();
             }
             Poll::Pending => {}
         }
@@ -128,3 +210,45 @@ impl<F: Future + 'static> AllocTaskStorage<F> {
         assert_sync(self)
     }
 }
+
+impl<F: Future + 'static> Joinable<F::Output> for AllocTaskStorage<F> {
+    fn poll_join(&self, waker: &Waker) -> Poll<Result<F::Output, JoinError>> {
+        if self.raw.state.load(Ordering::Acquire) & STATE_SPAWNED != 0 {
+            critical_section::with(|cs| *self.joiner.borrow(cs).borrow_mut() = Some(waker.clone()));
+
+            // Re-check after registering: the task may have completed between the load above
+            // and registering the waker, in which case we'd otherwise wait forever.
+            if self.raw.state.load(Ordering::Acquire) & STATE_SPAWNED != 0 {
+                return Poll::Pending;
+            }
+        }
+
+        // A stored output always wins over a concurrent `abort()`: the task already ran to
+        // completion and produced a real result, so a racing abort (e.g. a caller cancelling
+        // on a timeout that fires right as the task finishes) must not discard it.
+        //
+        // NOTE(unsafe): the task has finished (STATE_SPAWNED is clear), so nothing else
+        // writes to `output` anymore; the Acquire load above synchronizes with the Release
+        // store in `poll`.
+        if let Some(output) = unsafe { (*self.output.get()).take() } {
+            return Poll::Ready(Ok(output));
+        }
+
+        if self.join_state.load(Ordering::Acquire) & JOIN_ABORTED != 0 {
+            return Poll::Ready(Err(JoinError { _private: () }));
+        }
+
+        Poll::Pending
+    }
+
+    fn abort(&self) {
+        // Don't cancel a task that's already finished: setting JOIN_ABORTED here would race
+        // with `poll` having already stored a real output, and (depending on ordering) could
+        // make `poll_join` report a spurious `JoinError` for a task that actually succeeded.
+        if self.raw.state.load(Ordering::Acquire) & STATE_SPAWNED == 0 {
+            return;
+        }
+        self.join_state.fetch_or(JOIN_ABORTED, Ordering::AcqRel);
+        wake_task(unsafe { TaskRef::from_ptr(&self.raw as *const TaskHeader) });
+    }
+}
@@ -0,0 +1,271 @@
+//! Async driver for PCA9555/TCA6424-style I2C GPIO expanders.
+//!
+//! The expander keeps a shadow copy of each 8-bit output register so
+//! individual pins can be driven without reading the hardware back, and
+//! exposes per-pin [`ExpanderPin`] handles implementing the `embedded-hal`
+//! digital traits.
+//!
+//! On top of that it offers a "virtual LED" layer: register a mapping from
+//! a logical LED index to a physical expander pin plus a status source
+//! (a closure, typically reading an `AtomicBool`), then spawn [`Expander::service`] as a
+//! task. It periodically recomputes the desired pin states from those
+//! status sources, diffs them against the shadow register, and issues an
+//! I2C write only when a port actually changed - so e.g. an `rx_ready`
+//! flag can drive a link-status LED through the expander without the
+//! application ever touching the I2C bus directly.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex as CsMutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::{Channel, Receiver};
+use embassy_time::{Duration, Timer};
+use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+use heapless::Vec;
+
+use crate::i2c::{Error as I2cError, Instance, I2c};
+
+/// Largest number of 8-bit ports supported by any chip variant we know about
+/// (TCA6424 has 3).
+const MAX_PORTS: usize = 3;
+
+/// Depth of the error channel drained by [`Expander::errors`].
+const ERROR_CHANNEL_DEPTH: usize = 4;
+
+/// Errors returned by the expander driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The underlying I2C transaction failed.
+    I2c(I2cError),
+    /// The requested port/pin is out of range for the configured chip, or
+    /// the LED registration table is full.
+    InvalidPin,
+}
+
+impl From<I2cError> for Error {
+    fn from(e: I2cError) -> Self {
+        Error::I2c(e)
+    }
+}
+
+/// Expander configuration: I2C address, register layout and LED poll rate.
+///
+/// Use [`ExpanderConfig::pca9555`] or [`ExpanderConfig::tca6424`] for the
+/// common chip variants, or build one by hand for a compatible part.
+pub struct ExpanderConfig {
+    /// 7-bit I2C address of the expander.
+    pub address: u8,
+    /// Number of 8-bit GPIO ports on the chip (2 for PCA9555, 3 for TCA6424).
+    pub ports: usize,
+    /// Register address of port 0's output register; ports are assumed
+    /// contiguous from there.
+    pub output_reg_base: u8,
+    /// How often [`Expander::service`] recomputes virtual LED states.
+    pub led_poll_interval: Duration,
+}
+
+impl ExpanderConfig {
+    /// Configuration for a PCA9555 (2x 8-bit ports) at `address`.
+    pub const fn pca9555(address: u8) -> Self {
+        Self {
+            address,
+            ports: 2,
+            output_reg_base: 0x02,
+            led_poll_interval: Duration::from_millis(100),
+        }
+    }
+
+    /// Configuration for a TCA6424 (3x 8-bit ports) at `address`.
+    pub const fn tca6424(address: u8) -> Self {
+        Self {
+            address,
+            ports: 3,
+            output_reg_base: 0x04,
+            led_poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// A registered virtual LED: a physical pin plus a status source.
+struct VirtualLed<'d> {
+    port: usize,
+    mask: u8,
+    status: &'d (dyn Fn() -> bool + Send + Sync),
+}
+
+/// An I2C GPIO expander with up to `LEDS` virtual LEDs registered on it.
+pub struct Expander<'d, T: Instance, const LEDS: usize> {
+    // Not behind a critical-section mutex: the actual bus transaction in
+    // `write_port` must run with interrupts enabled (see its doc comment),
+    // and a plain `RefCell` is enough to catch accidental reentrancy since
+    // nothing here ever calls back into the expander from an interrupt.
+    i2c: RefCell<I2c<'d, T>>,
+    shadow: CsMutex<RefCell<[u8; MAX_PORTS]>>,
+    leds: CsMutex<RefCell<Vec<VirtualLed<'d>, LEDS>>>,
+    errors: Channel<CriticalSectionRawMutex, Error, ERROR_CHANNEL_DEPTH>,
+    config: ExpanderConfig,
+}
+
+impl<'d, T: Instance, const LEDS: usize> Expander<'d, T, LEDS> {
+    /// Create a new expander driver around an already-configured [`I2c`].
+    ///
+    /// Set `config.timeout` on the `I2c`'s own config before constructing it
+    /// so a stuck bus can't wedge [`Expander::service`] forever.
+    pub fn new(i2c: I2c<'d, T>, config: ExpanderConfig) -> Self {
+        assert!(config.ports <= MAX_PORTS, "chip has more ports than supported");
+        Self {
+            i2c: RefCell::new(i2c),
+            shadow: CsMutex::new(RefCell::new([0; MAX_PORTS])),
+            leds: CsMutex::new(RefCell::new(Vec::new())),
+            errors: Channel::new(),
+            config,
+        }
+    }
+
+    /// Register a virtual LED at `port`/`pin`, driven by `status`.
+    ///
+    /// `status` is polled from [`Expander::service`] at `led_poll_interval`;
+    /// it is typically a closure reading an `AtomicBool` set elsewhere (e.g.
+    /// from a link-status interrupt).
+    pub fn register_led(
+        &self,
+        port: usize,
+        pin: u8,
+        status: &'d (dyn Fn() -> bool + Send + Sync),
+    ) -> Result<(), Error> {
+        if port >= self.config.ports || pin >= 8 {
+            return Err(Error::InvalidPin);
+        }
+        critical_section::with(|cs| {
+            self.leds
+                .borrow(cs)
+                .borrow_mut()
+                .push(VirtualLed { port, mask: 1 << pin, status })
+                .map_err(|_| Error::InvalidPin)
+        })
+    }
+
+    /// Get an [`ExpanderPin`] for direct, application-driven control of a pin.
+    ///
+    /// This is independent of the virtual LED mechanism: writes through the
+    /// returned handle go straight to the shadow register and the bus.
+    pub fn pin(&self, port: usize, pin: u8) -> Result<ExpanderPin<'_, 'd, T, LEDS>, Error> {
+        if port >= self.config.ports || pin >= 8 {
+            return Err(Error::InvalidPin);
+        }
+        Ok(ExpanderPin {
+            expander: self,
+            port,
+            mask: 1 << pin,
+        })
+    }
+
+    /// Receiver for I2C errors encountered by [`Expander::service`].
+    ///
+    /// Repeated bus failures are pushed here instead of being retried
+    /// silently; the application decides how to react (log, reset the bus,
+    /// panic, ...).
+    pub fn errors(&self) -> Receiver<'_, CriticalSectionRawMutex, Error, ERROR_CHANNEL_DEPTH> {
+        self.errors.receiver()
+    }
+
+    /// Write `value` to `port`'s output register and update the shadow.
+    ///
+    /// The I2C transaction itself runs outside any critical section: the
+    /// whole point of `config.timeout` is to bound how long a stuck bus can
+    /// wedge the caller, and `embassy_time`'s tick (which the timeout is
+    /// measured against) needs its own interrupt to advance. Masking
+    /// interrupts for the transaction would make that timeout unable to
+    /// ever fire. Only the shadow-register update needs mutual exclusion,
+    /// so that's the only part taken under `critical_section`.
+    fn write_port(&self, port: usize, value: u8) -> Result<(), Error> {
+        let reg = self.config.output_reg_base + port as u8;
+        self.i2c.borrow_mut().blocking_write(self.config.address, &[reg, value])?;
+        critical_section::with(|cs| self.shadow.borrow(cs).borrow_mut()[port] = value);
+        Ok(())
+    }
+
+    fn shadow_byte(&self, port: usize) -> u8 {
+        critical_section::with(|cs| self.shadow.borrow(cs).borrow()[port])
+    }
+
+    fn shadow_bit(&self, port: usize, mask: u8) -> bool {
+        self.shadow_byte(port) & mask != 0
+    }
+
+    /// Run the virtual LED service loop. Spawn this as a task.
+    ///
+    /// Every `led_poll_interval`, recomputes each registered LED's desired
+    /// state, coalesces all pins belonging to the same port into a single
+    /// shadow-register update, and issues one I2C write per changed port.
+    /// Write failures are pushed onto [`Expander::errors`] rather than
+    /// retried silently.
+    pub async fn service(&self) -> ! {
+        loop {
+            Timer::after(self.config.led_poll_interval).await;
+
+            let mut desired = [0u8; MAX_PORTS];
+            let mut touched = [0u8; MAX_PORTS];
+            critical_section::with(|cs| {
+                for led in self.leds.borrow(cs).borrow().iter() {
+                    touched[led.port] |= led.mask;
+                    if (led.status)() {
+                        desired[led.port] |= led.mask;
+                    }
+                }
+            });
+
+            for port in 0..self.config.ports {
+                if touched[port] == 0 {
+                    continue;
+                }
+                let current = self.shadow_byte(port);
+                let next = (current & !touched[port]) | (desired[port] & touched[port]);
+                if next == current {
+                    continue;
+                }
+                if let Err(e) = self.write_port(port, next) {
+                    let _ = self.errors.try_send(e);
+                }
+            }
+        }
+    }
+}
+
+/// A handle to a single pin on an [`Expander`], implementing the
+/// `embedded-hal` digital output traits.
+pub struct ExpanderPin<'a, 'd, T: Instance, const LEDS: usize> {
+    expander: &'a Expander<'d, T, LEDS>,
+    port: usize,
+    mask: u8,
+}
+
+impl<'a, 'd, T: Instance, const LEDS: usize> OutputPin for ExpanderPin<'a, 'd, T, LEDS> {
+    type Error = Error;
+
+    fn set_low(&mut self) -> Result<(), Error> {
+        let byte = self.expander.shadow_byte(self.port);
+        if byte & self.mask != 0 {
+            self.expander.write_port(self.port, byte & !self.mask)?;
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Error> {
+        let byte = self.expander.shadow_byte(self.port);
+        if byte & self.mask == 0 {
+            self.expander.write_port(self.port, byte | self.mask)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'd, T: Instance, const LEDS: usize> StatefulOutputPin for ExpanderPin<'a, 'd, T, LEDS> {
+    fn is_set_high(&self) -> Result<bool, Error> {
+        Ok(self.expander.shadow_bit(self.port, self.mask))
+    }
+
+    fn is_set_low(&self) -> Result<bool, Error> {
+        Ok(!self.expander.shadow_bit(self.port, self.mask))
+    }
+}
@@ -0,0 +1,291 @@
+//! DMA-driven parallel frame/data grabber.
+//!
+//! Captures a parallel data stream (DCMI-style pixel clock + HSYNC/VSYNC, or
+//! a generic timer-gated parallel port) into memory using the DMA
+//! infrastructure wired up in `build.rs` (the generated `on_irq` handlers
+//! for `crate::dma::dma`/`crate::dma::bdma`). [`Grabber::capture_frame_ring`]
+//! alternates which of two buffers each capture writes into, so the frame
+//! from call N is left untouched while call N+1 fills the other buffer.
+//! Both buffers stay mutably borrowed for the duration of each call, so a
+//! frame can only be read between calls - each capture is still a fully
+//! sequential wait-then-DMA, not a pipelined/overlapped transfer.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_hal_common::{into_ref, PeripheralRef};
+use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::Duration;
+
+use crate::dma::{Channel, Transfer, TransferOptions};
+use crate::gpio::{AFType, AnyPin, Pin};
+use crate::rcc::RccPeripheral;
+use crate::{peripherals, Peripheral};
+
+/// Errors returned by the grabber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The frame-start signal didn't arrive before `config.timeout`.
+    Timeout,
+    /// A new frame started before the DMA transfer for the previous one
+    /// finished (the pixel clock is faster than the DMA can keep up with).
+    Overrun,
+}
+
+/// Grabber configuration.
+pub struct Config {
+    /// Number of 8-bit samples per line. Used to report [`FrameInfo`].
+    pub line_length: usize,
+    /// Number of lines per frame. Used to report [`FrameInfo`] and to decide
+    /// when a frame transfer is complete.
+    pub lines_per_frame: usize,
+    /// How long to wait for the frame-start (VSYNC) signal before giving up
+    /// with [`Error::Timeout`]. `None` waits forever.
+    pub timeout: Option<Duration>,
+}
+
+/// Dimensions of a captured frame, returned by [`Grabber::capture_frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Number of 8-bit samples per line.
+    pub line_length: usize,
+    /// Number of lines captured.
+    pub lines: usize,
+}
+
+pub(crate) mod sealed {
+    pub trait Instance {
+        fn regs() -> crate::pac::dcmi::Dcmi;
+        fn state() -> &'static super::State;
+    }
+}
+
+/// Parallel-capture peripheral instance (e.g. DCMI).
+pub trait Instance: sealed::Instance + RccPeripheral + 'static {}
+
+pub(crate) struct State {
+    pub waker: AtomicWaker,
+    /// Set by the interrupt handler when a frame-start edge is seen, and
+    /// consumed by `wait_for_frame_start`.
+    pub frame_ready: AtomicBool,
+    /// Set for as long as a DMA transfer is in flight (from just before it's
+    /// armed in `capture_frame` to just after it completes), so the
+    /// interrupt handler can tell a genuine overrun (a new frame-start while
+    /// the previous frame is still being transferred) apart from the next
+    /// frame's ordinary frame-start edge.
+    pub capturing: AtomicBool,
+    /// Set by the interrupt handler when a frame-start edge arrives while
+    /// `capturing` is set (i.e. the pixel clock outran the DMA), and
+    /// consumed by `capture_frame` once the in-flight transfer completes.
+    pub overrun: AtomicBool,
+}
+
+impl State {
+    pub const fn new() -> Self {
+        Self {
+            waker: AtomicWaker::new(),
+            frame_ready: AtomicBool::new(false),
+            capturing: AtomicBool::new(false),
+            overrun: AtomicBool::new(false),
+        }
+    }
+}
+
+/// A GPIO pin routed to the parallel capture peripheral (pixel clock,
+/// HSYNC, VSYNC, or one of the data lines), with its alternate-function
+/// number fixed at construction.
+pub struct GrabberPin<'d> {
+    pin: PeripheralRef<'d, AnyPin>,
+    af_num: u8,
+}
+
+impl<'d> GrabberPin<'d> {
+    /// Wrap `pin` for use with [`Grabber::new`], configured for AF `af_num`
+    /// (see your chip's alternate-function table for the DCMI signal you're
+    /// routing it to).
+    pub fn new(pin: impl Peripheral<P = impl Pin> + 'd, af_num: u8) -> Self {
+        into_ref!(pin);
+        Self {
+            pin: pin.map_into(),
+            af_num,
+        }
+    }
+
+    fn configure(&mut self) {
+        self.pin.set_as_af(self.af_num, AFType::Input);
+    }
+}
+
+/// The eleven pins a parallel 8-bit capture peripheral needs: pixel clock,
+/// HSYNC, VSYNC, and data lines D0-D7.
+pub struct GrabberPins<'d> {
+    pub pclk: GrabberPin<'d>,
+    pub hsync: GrabberPin<'d>,
+    pub vsync: GrabberPin<'d>,
+    pub data: [GrabberPin<'d>; 8],
+}
+
+/// DMA-driven parallel frame grabber.
+///
+/// `capture_frame` arms a DMA transfer synchronized to the frame-start
+/// signal (VSYNC), awaits transfer-complete, and hands back the captured
+/// line/byte count. For continuous capture, alternate destination buffers
+/// between two calls so one frame can be processed while the next is
+/// captured - the grabber itself holds no buffers, it just drives the DMA
+/// into whichever buffer you pass it.
+pub struct Grabber<'d, T: Instance, Dma: Channel> {
+    _peri: PeripheralRef<'d, T>,
+    _pins: GrabberPins<'d>,
+    dma: PeripheralRef<'d, Dma>,
+    config: Config,
+}
+
+impl<'d, T: Instance, Dma: Channel> Grabber<'d, T, Dma> {
+    /// Create a new grabber on `peri`, reading samples through `pins` and
+    /// transferring them out via `dma`.
+    ///
+    /// `pins` are routed to their alternate function here and held for the
+    /// lifetime of the `Grabber`, so they can't be reused elsewhere while
+    /// it's alive.
+    pub fn new(peri: impl Peripheral<P = T> + 'd, mut pins: GrabberPins<'d>, dma: impl Peripheral<P = Dma> + 'd, config: Config) -> Self {
+        into_ref!(peri, dma);
+
+        pins.pclk.configure();
+        pins.hsync.configure();
+        pins.vsync.configure();
+        for data_pin in &mut pins.data {
+            data_pin.configure();
+        }
+
+        T::enable();
+        T::reset();
+
+        Self {
+            _peri: peri,
+            _pins: pins,
+            dma,
+            config,
+        }
+    }
+
+    /// Capture a single frame into `buf`.
+    ///
+    /// Waits for the frame-start (VSYNC) edge, then arms the DMA transfer
+    /// and awaits DMA transfer-complete. `buf` must be at least
+    /// `config.line_length * config.lines_per_frame` bytes.
+    ///
+    /// Returns [`Error::Overrun`] if a new frame-start edge arrives while
+    /// this call's own DMA transfer is still in flight - the data in `buf`
+    /// is a torn mix of the requested frame and the one that cut it off, so
+    /// callers should discard it rather than treat it as complete.
+    pub async fn capture_frame(&mut self, buf: &mut [u8]) -> Result<FrameInfo, Error> {
+        let total = self.config.line_length * self.config.lines_per_frame;
+        assert!(buf.len() >= total, "buffer too small for a full frame");
+
+        let src = T::regs().dr().as_ptr() as *mut u32;
+        let request = self.dma.request();
+        let options = TransferOptions::default();
+
+        // Wait for VSYNC / frame-start before arming so we don't capture a
+        // partial line from the middle of an in-progress frame.
+        self.wait_for_frame_start().await?;
+
+        let state = T::state();
+        state.capturing.store(true, Ordering::Release);
+        let transfer = unsafe { Transfer::new_read(&mut self.dma, request, src, &mut buf[..total], options) };
+        transfer.await;
+        state.capturing.store(false, Ordering::Release);
+
+        if state.overrun.swap(false, Ordering::AcqRel) {
+            return Err(Error::Overrun);
+        }
+
+        Ok(FrameInfo {
+            line_length: self.config.line_length,
+            lines: self.config.lines_per_frame,
+        })
+    }
+
+    /// Capture frames into `buffers`, alternating between the two on each
+    /// call so the frame from call N is left untouched while call N+1 fills
+    /// the other buffer. Both buffers are mutably borrowed for the whole
+    /// call, so the previous frame can only be read between calls.
+    ///
+    /// This is alternating single-shot capture, not pipelined double
+    /// buffering: each call still fully waits for its own frame-start and
+    /// DMA transfer before returning, so there's no capture happening while
+    /// the application is between calls.
+    ///
+    /// `next` (0 or 1) selects the buffer to fill and is flipped to the
+    /// other index on success, so `buffers[1 - *next]` then holds the frame
+    /// just captured; on error `next` is left unchanged. Returns the
+    /// captured frame's [`FrameInfo`].
+    pub async fn capture_frame_ring(&mut self, buffers: &mut [&mut [u8]; 2], next: &mut usize) -> Result<FrameInfo, Error> {
+        let idx = *next;
+        let info = self.capture_frame(buffers[idx]).await?;
+        *next = 1 - idx;
+        Ok(info)
+    }
+
+    async fn wait_for_frame_start(&self) -> Result<(), Error> {
+        let wait = core::future::poll_fn(|cx| {
+            let state = T::state();
+            state.waker.register(cx.waker());
+            if state.frame_ready.swap(false, Ordering::AcqRel) {
+                core::task::Poll::Ready(())
+            } else {
+                core::task::Poll::Pending
+            }
+        });
+
+        match self.config.timeout {
+            Some(timeout) => embassy_time::with_timeout(timeout, wait).await.map_err(|_| Error::Timeout),
+            None => {
+                wait.await;
+                Ok(())
+            }
+        }
+    }
+}
+
+unsafe fn on_interrupt<T: Instance>() {
+    let regs = T::regs();
+    let mis = regs.mis().read();
+    if mis.frame() {
+        regs.icr().write(|w| w.set_frame(true));
+
+        let state = T::state();
+        if state.capturing.load(Ordering::Acquire) {
+            // A new frame-start arrived while the current capture's DMA
+            // transfer is still in flight - the pixel clock outran the DMA.
+            // `capture_frame` checks this once its transfer completes;
+            // don't also store it into `frame_ready`, since this edge
+            // belongs to the frame that just cut the current one off, not
+            // to the next `wait_for_frame_start`.
+            state.overrun.store(true, Ordering::Release);
+        } else {
+            state.frame_ready.store(true, Ordering::Release);
+        }
+        state.waker.wake();
+    }
+}
+
+foreach_peripheral!(
+    (dcmi, $inst:ident) => {
+        impl sealed::Instance for peripherals::$inst {
+            fn regs() -> crate::pac::dcmi::Dcmi {
+                crate::pac::$inst
+            }
+            fn state() -> &'static State {
+                static STATE: State = State::new();
+                &STATE
+            }
+        }
+
+        impl Instance for peripherals::$inst {}
+
+        #[crate::interrupt]
+        unsafe fn $inst() {
+            on_interrupt::<peripherals::$inst>();
+        }
+    };
+);
@@ -0,0 +1,267 @@
+//! `key=value` runtime configuration loader.
+//!
+//! Parses a small `key=value` text blob - read from an SD card file, a
+//! flash region, or just a RAM buffer baked in at build time - and exposes
+//! typed getters so a board can override compiled-in defaults (network
+//! MAC/IPv4/IPv6 addresses via [`NetConfig`]) without a reflash. Unset keys
+//! fall through to `None`/`Ok(None)`, so callers simply keep whatever
+//! default they already had.
+//!
+//! This is a standalone helper, not an `embassy_stm32::init` integration:
+//! clock selection isn't covered, and nothing here touches `init`'s own
+//! `Config`. Call [`NetConfig::load`] yourself and hand the result to
+//! whatever consumes those fields (e.g. your networking stack).
+
+use core::str::FromStr;
+
+/// Errors produced while parsing a `key=value` configuration blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A key was present but its value didn't parse as the requested type.
+    Malformed,
+}
+
+/// A parsed view over a `key=value` configuration blob.
+///
+/// Blank lines and lines starting with `#` are ignored; keys and values are
+/// trimmed of surrounding whitespace. The blob is not copied - `bytes` is
+/// re-scanned on every lookup, which is cheap at the sizes this is meant
+/// for (a handful of board-configuration keys).
+pub struct RuntimeConfig<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RuntimeConfig<'a> {
+    /// Parse `bytes` as a `key=value` configuration blob.
+    pub const fn from_bytes(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.bytes.split(|&b| b == b'\n').filter_map(|line| {
+            let line = trim(line);
+            if line.is_empty() || line[0] == b'#' {
+                return None;
+            }
+            let eq = line.iter().position(|&b| b == b'=')?;
+            let key = trim(&line[..eq]);
+            let value = trim(&line[eq + 1..]);
+            Some((core::str::from_utf8(key).ok()?, core::str::from_utf8(value).ok()?))
+        })
+    }
+
+    fn get_raw(&self, key: &str) -> Option<&'a str> {
+        self.entries().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Look up `key` and parse it as a `u32`. A `0x` prefix is read as hex.
+    pub fn get_u32(&self, key: &str) -> Result<Option<u32>, Error> {
+        let Some(v) = self.get_raw(key) else { return Ok(None) };
+        let parsed = match v.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => u32::from_str(v),
+        };
+        parsed.map(Some).map_err(|_| Error::Malformed)
+    }
+
+    /// Look up `key` and parse it as a colon-separated MAC address, e.g.
+    /// `02:00:00:00:00:01`.
+    pub fn get_mac(&self, key: &str) -> Result<Option<[u8; 6]>, Error> {
+        let Some(v) = self.get_raw(key) else { return Ok(None) };
+        let mut out = [0u8; 6];
+        let mut parts = v.split(':');
+        for byte in out.iter_mut() {
+            let part = parts.next().ok_or(Error::Malformed)?;
+            *byte = u8::from_str_radix(part, 16).map_err(|_| Error::Malformed)?;
+        }
+        if parts.next().is_some() {
+            return Err(Error::Malformed);
+        }
+        Ok(Some(out))
+    }
+
+    /// Look up `key` and parse it as a dotted-quad IPv4 address, e.g.
+    /// `192.168.1.1`.
+    pub fn get_ipv4(&self, key: &str) -> Result<Option<[u8; 4]>, Error> {
+        let Some(v) = self.get_raw(key) else { return Ok(None) };
+        let mut out = [0u8; 4];
+        let mut parts = v.split('.');
+        for byte in out.iter_mut() {
+            let part = parts.next().ok_or(Error::Malformed)?;
+            *byte = u8::from_str(part).map_err(|_| Error::Malformed)?;
+        }
+        if parts.next().is_some() {
+            return Err(Error::Malformed);
+        }
+        Ok(Some(out))
+    }
+
+    /// Look up `key` and parse it as a colon-separated IPv6 address with 8
+    /// explicit groups, e.g. `fe80:0:0:0:0:0:0:1`. `::` compression is not
+    /// supported; expand it in the source file instead.
+    pub fn get_ipv6(&self, key: &str) -> Result<Option<[u8; 16]>, Error> {
+        let Some(v) = self.get_raw(key) else { return Ok(None) };
+        let mut out = [0u8; 16];
+        let mut parts = v.split(':');
+        for chunk in out.chunks_mut(2) {
+            let part = parts.next().ok_or(Error::Malformed)?;
+            let group = u16::from_str_radix(part, 16).map_err(|_| Error::Malformed)?;
+            chunk.copy_from_slice(&group.to_be_bytes());
+        }
+        if parts.next().is_some() {
+            return Err(Error::Malformed);
+        }
+        Ok(Some(out))
+    }
+}
+
+/// Implemented by configuration structs that can be field-overridden by a
+/// [`RuntimeConfig`] blob, e.g. [`NetConfig`].
+///
+/// `apply_overrides` is meant to be called on a `Default::default()` (or
+/// otherwise compiled-in-default) value before it's handed off to whatever
+/// consumes it; keys absent from `overrides` leave the corresponding field
+/// untouched.
+pub trait Overridable {
+    /// Apply any keys present in `overrides` on top of `self`'s current
+    /// values.
+    fn apply_overrides(&mut self, overrides: &RuntimeConfig) -> Result<(), Error>;
+}
+
+/// Network identity fields commonly read from a board's runtime
+/// configuration blob: MAC/IPv4/IPv6 addresses.
+///
+/// This is deliberately not wired into `embassy_stm32::init` - that
+/// function's `Config` covers clock/peripheral setup only, and clock
+/// selection from a runtime blob isn't implemented here. Callers merge
+/// `NetConfig`'s fields into whatever networking stack they're using (e.g.
+/// `embassy_net::Config`) after calling [`NetConfig::load`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetConfig {
+    /// MAC address, overridden by the `mac` key.
+    pub mac: [u8; 6],
+    /// IPv4 address, overridden by the `ipv4` key.
+    pub ipv4: [u8; 4],
+    /// IPv6 address, overridden by the `ipv6` key.
+    pub ipv6: [u8; 16],
+}
+
+impl Default for NetConfig {
+    fn default() -> Self {
+        Self {
+            mac: [0x02, 0x00, 0x00, 0x00, 0x00, 0x01],
+            ipv4: [192, 168, 1, 1],
+            ipv6: [0; 16],
+        }
+    }
+}
+
+impl Overridable for NetConfig {
+    fn apply_overrides(&mut self, overrides: &RuntimeConfig) -> Result<(), Error> {
+        if let Some(mac) = overrides.get_mac("mac")? {
+            self.mac = mac;
+        }
+        if let Some(ipv4) = overrides.get_ipv4("ipv4")? {
+            self.ipv4 = ipv4;
+        }
+        if let Some(ipv6) = overrides.get_ipv6("ipv6")? {
+            self.ipv6 = ipv6;
+        }
+        Ok(())
+    }
+}
+
+impl NetConfig {
+    /// Start from [`NetConfig::default`] and apply any overrides present in
+    /// `bytes`, in one step.
+    pub fn load(bytes: &[u8]) -> Result<Self, Error> {
+        let mut config = Self::default();
+        config.apply_overrides(&RuntimeConfig::from_bytes(bytes))?;
+        Ok(config)
+    }
+}
+
+fn trim(mut bytes: &[u8]) -> &[u8] {
+    while let [b' ' | b'\t' | b'\r', rest @ ..] = bytes {
+        bytes = rest;
+    }
+    while let [rest @ .., b' ' | b'\t' | b'\r'] = bytes {
+        bytes = rest;
+    }
+    bytes
+}
+
+// Unlike the rest of this crate, this module has no hardware dependency, so
+// it's worth covering with plain host-run tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u32_decimal_and_hex() {
+        let cfg = RuntimeConfig::from_bytes(b"a=42\nb=0x2a\n");
+        assert_eq!(cfg.get_u32("a"), Ok(Some(42)));
+        assert_eq!(cfg.get_u32("b"), Ok(Some(42)));
+        assert_eq!(cfg.get_u32("missing"), Ok(None));
+    }
+
+    #[test]
+    fn u32_malformed() {
+        let cfg = RuntimeConfig::from_bytes(b"a=not-a-number\n");
+        assert_eq!(cfg.get_u32("a"), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn mac_parses_and_rejects_malformed() {
+        let cfg = RuntimeConfig::from_bytes(b"mac=02:00:00:00:00:01\n");
+        assert_eq!(cfg.get_mac("mac"), Ok(Some([0x02, 0x00, 0x00, 0x00, 0x00, 0x01])));
+
+        let short = RuntimeConfig::from_bytes(b"mac=02:00:00\n");
+        assert_eq!(short.get_mac("mac"), Err(Error::Malformed));
+
+        let long = RuntimeConfig::from_bytes(b"mac=02:00:00:00:00:01:02\n");
+        assert_eq!(long.get_mac("mac"), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn ipv4_parses_and_rejects_malformed() {
+        let cfg = RuntimeConfig::from_bytes(b"ipv4=192.168.1.50\n");
+        assert_eq!(cfg.get_ipv4("ipv4"), Ok(Some([192, 168, 1, 50])));
+
+        let bad = RuntimeConfig::from_bytes(b"ipv4=192.168.1\n");
+        assert_eq!(bad.get_ipv4("ipv4"), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn ipv6_parses_and_rejects_malformed() {
+        let cfg = RuntimeConfig::from_bytes(b"ipv6=fe80:0:0:0:0:0:0:1\n");
+        assert_eq!(
+            cfg.get_ipv6("ipv6"),
+            Ok(Some([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]))
+        );
+
+        let bad = RuntimeConfig::from_bytes(b"ipv6=fe80:0:0\n");
+        assert_eq!(bad.get_ipv6("ipv6"), Err(Error::Malformed));
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_ignored() {
+        let cfg = RuntimeConfig::from_bytes(b"# a comment\n\n  \na=1\n# trailing\n");
+        assert_eq!(cfg.get_u32("a"), Ok(Some(1)));
+    }
+
+    #[test]
+    fn line_without_equals_is_silently_skipped() {
+        let cfg = RuntimeConfig::from_bytes(b"not-a-kv-line\na=1\n");
+        assert_eq!(cfg.get_u32("a"), Ok(Some(1)));
+        assert_eq!(cfg.get_u32("not-a-kv-line"), Ok(None));
+    }
+
+    #[test]
+    fn net_config_load_applies_only_present_keys() {
+        let config = NetConfig::load(b"mac=02:00:00:00:00:02\n").unwrap();
+        assert_eq!(config.mac, [0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(config.ipv4, NetConfig::default().ipv4);
+        assert_eq!(config.ipv6, NetConfig::default().ipv6);
+    }
+}
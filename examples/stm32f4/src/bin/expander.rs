@@ -0,0 +1,55 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::expander::{Expander, ExpanderConfig};
+use embassy_stm32::i2c::{Config, I2c};
+use embassy_stm32::time::Hertz;
+use embassy_time::Duration;
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+const ADDRESS: u8 = 0x20;
+
+/// Flipped by whatever's watching the link; read by the expander's virtual
+/// LED service task below.
+static RX_READY: AtomicBool = AtomicBool::new(false);
+
+fn rx_ready() -> bool {
+    RX_READY.load(Ordering::Relaxed)
+}
+
+static EXPANDER: StaticCell<Expander<'static, embassy_stm32::peripherals::I2C2, 1>> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn expander_service(expander: &'static Expander<'static, embassy_stm32::peripherals::I2C2, 1>) {
+    expander.service().await
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    info!("Hello world!");
+    let p = embassy_stm32::init(Default::default());
+
+    let mut config = Config::default();
+    // Setting a timeout prevents the expander service task from wedging
+    // forever if the bus locks up.
+    config.timeout = Some(Duration::from_millis(100));
+
+    let i2c = I2c::new(p.I2C2, p.PB10, p.PB11, Hertz(100_000), config);
+
+    let expander = EXPANDER.init(Expander::new(i2c, ExpanderConfig::pca9555(ADDRESS)));
+    unwrap!(expander.register_led(0, 0, &rx_ready));
+
+    unwrap!(spawner.spawn(expander_service(expander)));
+
+    loop {
+        match expander.errors().receive().await {
+            e => defmt::error!("expander I2C error: {:?}", defmt::Debug2Format(&e)),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::grabber::{Config, Grabber, GrabberPin, GrabberPins};
+use embassy_time::Duration;
+use {defmt_rtt as _, panic_probe as _};
+
+const LINE_LENGTH: usize = 640;
+const LINES_PER_FRAME: usize = 480;
+const FRAME_BYTES: usize = LINE_LENGTH * LINES_PER_FRAME;
+
+static mut BUF_A: [u8; FRAME_BYTES] = [0; FRAME_BYTES];
+static mut BUF_B: [u8; FRAME_BYTES] = [0; FRAME_BYTES];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) -> ! {
+    info!("Hello world!");
+    let p = embassy_stm32::init(Default::default());
+
+    // AF numbers below are DCMI's alternate function on STM32F4; check your
+    // chip's datasheet if porting this.
+    let pins = GrabberPins {
+        pclk: GrabberPin::new(p.PA6, 13),
+        hsync: GrabberPin::new(p.PA4, 13),
+        vsync: GrabberPin::new(p.PB7, 13),
+        data: [
+            GrabberPin::new(p.PA9, 13),
+            GrabberPin::new(p.PA10, 13),
+            GrabberPin::new(p.PE4, 13),
+            GrabberPin::new(p.PE5, 13),
+            GrabberPin::new(p.PE6, 13),
+            GrabberPin::new(p.PB6, 13),
+            GrabberPin::new(p.PB8, 13),
+            GrabberPin::new(p.PB9, 13),
+        ],
+    };
+
+    let mut grabber = Grabber::new(
+        p.DCMI,
+        pins,
+        p.DMA2_CH7,
+        Config {
+            line_length: LINE_LENGTH,
+            lines_per_frame: LINES_PER_FRAME,
+            // Bounds the wait for frame-start (VSYNC); the DMA transfer
+            // that follows has no timeout of its own.
+            timeout: Some(Duration::from_millis(500)),
+        },
+    );
+
+    let mut buffers = unsafe { [&mut BUF_A[..], &mut BUF_B[..]] };
+    let mut next = 0;
+
+    loop {
+        let info = unwrap!(grabber.capture_frame_ring(&mut buffers, &mut next).await);
+        info!("captured frame: {}x{}", info.line_length, info.lines);
+    }
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::runtime_config::NetConfig;
+use {defmt_rtt as _, panic_probe as _};
+
+// In a real board this would be read from an SD card file or a flash
+// region; here it's just baked into the binary for illustration.
+static CONFIG_BLOB: &[u8] = b"\
+    # board overrides\n\
+    mac=02:00:00:00:00:01\n\
+    ipv4=192.168.1.50\n\
+";
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) -> ! {
+    info!("Hello world!");
+
+    // Starts from `NetConfig::default()` and overlays whatever keys
+    // `CONFIG_BLOB` sets; a board shipped without the blob (or with it
+    // wiped) still boots with sane defaults.
+    let net_config = unwrap!(NetConfig::load(CONFIG_BLOB));
+    info!("mac: {:?}, ipv4: {:?}", net_config.mac, net_config.ipv4);
+
+    let _p = embassy_stm32::init(Default::default());
+
+    // A real application would hand `net_config` to its networking stack
+    // here, e.g. `embassy_net::Config::ipv4_static(...)`.
+
+    loop {}
+}